@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+/// Parsed front matter plus the remaining (un-fenced) page body.
+pub struct FrontMatter {
+    pub data: HashMap<String, String>,
+    pub body: String,
+}
+
+/// Parses a leading `+++ ... +++` (TOML-style `key = value`) or
+/// `--- ... ---` (YAML-style `key: value`) front-matter fence off the start
+/// of `input`, returning the parsed key/value data and the body with the
+/// fence stripped. Content with no recognized fence is returned unchanged
+/// with empty data.
+pub fn parse(input: &str) -> FrontMatter {
+    if let Some(rest) = input.strip_prefix("+++\n") {
+        if let Some((data, body)) = split_fence(rest, "+++", '=') {
+            return FrontMatter { data, body };
+        }
+    } else if let Some(rest) = input.strip_prefix("---\n") {
+        if let Some((data, body)) = split_fence(rest, "---", ':') {
+            return FrontMatter { data, body };
+        }
+    }
+
+    FrontMatter { data: HashMap::new(), body: input.to_string() }
+}
+
+fn split_fence(rest: &str, fence: &str, sep: char) -> Option<(HashMap<String, String>, String)> {
+    let closing = format!("\n{}", fence);
+    let end = rest.find(&closing)?;
+    let block = &rest[..end];
+    let body = rest[end + closing.len()..].trim_start_matches('\n').to_string();
+    Some((parse_key_values(block, sep), body))
+}
+
+fn parse_key_values(block: &str, sep: char) -> HashMap<String, String> {
+    let mut data = HashMap::new();
+    for line in block.lines() {
+        if let Some(idx) = line.find(sep) {
+            let key = line[..idx].trim().to_string();
+            let value = line[idx + 1..].trim().trim_matches('"').to_string();
+            if !key.is_empty() {
+                data.insert(key, value);
+            }
+        }
+    }
+    data
+}
+
+/// Whether the page's front matter marks it as a draft (`draft = true`),
+/// meaning `build_site` should skip writing it to `output/`.
+pub fn is_draft(data: &HashMap<String, String>) -> bool {
+    data.get("draft").map(|v| v == "true").unwrap_or(false)
+}
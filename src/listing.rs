@@ -0,0 +1,161 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+struct Entry {
+    name: String,
+    is_dir: bool,
+    size: u64,
+    modified: SystemTime,
+}
+
+/// Renders a simple file-browser-style directory listing for `dir`:
+/// directories first, then files, both alphabetical, each tagged with a
+/// file-type class so templates/CSS can style them like a typical OS file
+/// browser.
+pub fn render_directory_listing(dir: &Path) -> io::Result<String> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        entries.push(Entry {
+            name: entry.file_name().to_string_lossy().into_owned(),
+            is_dir: metadata.is_dir(),
+            size: metadata.len(),
+            modified: metadata.modified().unwrap_or(UNIX_EPOCH),
+        });
+    }
+
+    entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+    });
+
+    let mut html = String::from("<ul class=\"directory-listing\">\n");
+    for entry in &entries {
+        let class = if entry.is_dir { "dir" } else { file_type_class(&entry.name) };
+        let href = if entry.is_dir {
+            format!("{}/", percent_encode(&entry.name))
+        } else {
+            percent_encode(&entry.name)
+        };
+        let size = if entry.is_dir { "-".to_string() } else { format_size(entry.size) };
+
+        html.push_str(&format!(
+            "<li class=\"entry {class}\"><a href=\"{href}\">{name}</a><span class=\"size\">{size}</span><span class=\"modified\">{modified}</span></li>\n",
+            class = class,
+            href = href,
+            name = html_escape(&entry.name),
+            size = size,
+            modified = format_modified(entry.modified),
+        ));
+    }
+    html.push_str("</ul>\n");
+
+    Ok(html)
+}
+
+/// Buckets a filename into a coarse file-type class (`archive`, `word`,
+/// `image`, `pdf`, `code`, ...) by its extension, defaulting to `file`.
+fn file_type_class(name: &str) -> &'static str {
+    let ext = Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    match ext.as_str() {
+        "zip" | "tar" | "gz" | "rar" | "7z" => "archive",
+        "doc" | "docx" | "rtf" | "odt" => "word",
+        "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" => "image",
+        "pdf" => "pdf",
+        "rs" | "js" | "ts" | "py" | "c" | "cpp" | "go" | "java" | "html" | "css" => "code",
+        "mp3" | "wav" | "flac" => "audio",
+        "mp4" | "mov" | "webm" => "video",
+        _ => "file",
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+fn format_modified(modified: SystemTime) -> String {
+    let secs = modified.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    civil_datetime(secs)
+}
+
+/// Formats Unix seconds as `YYYY-MM-DD HH:MM` UTC without pulling in a date
+/// crate, using Howard Hinnant's civil-from-days algorithm.
+fn civil_datetime(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let time_of_day = secs % 86_400;
+    let (hour, minute) = (time_of_day / 3600, (time_of_day % 3600) / 60);
+
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = (z - era * 146_097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = if month <= 2 { y + 1 } else { y };
+
+    format!("{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, hour, minute)
+}
+
+/// Percent-encodes `name` for use in an `href`, leaving only RFC 3986
+/// unreserved characters unescaped. This also keeps the result free of `"`,
+/// `<`, `>`, and `&`, so it's safe to interpolate straight into an HTML
+/// attribute without a separate escaping pass.
+fn percent_encode(name: &str) -> String {
+    let mut encoded = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char);
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::percent_encode;
+
+    #[test]
+    fn percent_encode_neutralizes_attribute_injection_characters() {
+        let encoded = percent_encode(r#"x" onmouseover="alert(1)"#);
+        assert_eq!(encoded, "x%22%20onmouseover%3D%22alert%281%29");
+        assert!(!encoded.contains('"'));
+        assert!(!encoded.contains('<'));
+        assert!(!encoded.contains('>'));
+        assert!(!encoded.contains('&'));
+    }
+
+    #[test]
+    fn percent_encode_leaves_unreserved_characters_alone() {
+        assert_eq!(percent_encode("report-v1_final.txt~"), "report-v1_final.txt~");
+    }
+}
@@ -1,141 +1,364 @@
-use std::net::{TcpListener, TcpStream};
+mod frontmatter;
+mod listing;
+mod markdown;
+mod mime;
+mod tls;
+mod watcher;
+
+use std::collections::HashMap;
+use std::net::TcpListener;
 use std::io::{Read, Write};
 use std::fs;
-use std::path::Path;
-use std::sync::{mpsc, Arc, Mutex};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Arc;
 use std::thread;
-use std::time::{Duration, SystemTime};
-use ws::{listen, CloseCode, Sender};
-use lazy_static::lazy_static;
-use std::sync::atomic::{AtomicBool, Ordering};
+
+use openssl::ssl::{SslAcceptor, SslStream};
+use ws::Sender;
+use ws::util::TcpStream as WsTcpStream;
+
+use frontmatter::is_draft;
+use markdown::markdown_to_html;
+use mime::content_type_for;
+use watcher::{watch_directories, ChangeKind};
+
+/// Dev-server behavior toggles that aren't part of the generated site
+/// itself, set once at startup.
+#[derive(Clone, Copy)]
+struct Config {
+    /// Render a directory listing for requests that resolve to a directory
+    /// with no `index.html`, instead of 404ing like a production site would.
+    auto_index: bool,
+    /// Serve over HTTPS (via `rustls`) instead of plaintext HTTP.
+    tls: bool,
+}
+
+impl Config {
+    fn from_env() -> Config {
+        let auto_index = env_flag("AUTO_INDEX");
+        let tls = env_flag("TLS");
+        Config { auto_index, tls }
+    }
+}
+
+fn env_flag(name: &str) -> bool {
+    std::env::var(name)
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
 
 fn main() {
     // Build the site initially
     build_site().unwrap();
 
+    let config = Config::from_env();
+
     // Set up the file watcher
     let (tx, rx) = mpsc::channel();
-    let server_control = Arc::new(Mutex::new(true));
-    let server_control_clone = Arc::clone(&server_control);
-
     thread::spawn(move || {
-        watch_content_directory(tx);
+        if let Err(e) = watch_directories(tx) {
+            println!("watch error: {:?}", e);
+        }
     });
 
-    // Start the web server and WebSocket server in separate threads
-    let server_thread = thread::spawn(move || {
-        start_server(server_control_clone);
-    });
+    // The WebSocket broadcaster is created once and reused for every reload;
+    // handle_connection re-reads files from disk per request, so the HTTP
+    // server never needs restarting either.
+    let broadcaster = start_ws_server(config.tls);
 
-    let ws_thread = thread::spawn(move || {
-        start_ws_server();
-    });
+    thread::spawn(move || rebuild_loop(rx, broadcaster));
 
-    // Watch for file changes and rebuild the site
+    start_server(config);
+}
+
+/// Watches for file-change notifications, rebuilds the affected part of the
+/// site, and broadcasts a LiveReload command to every connected client.
+fn rebuild_loop(rx: mpsc::Receiver<ChangeKind>, broadcaster: Sender) {
     loop {
-        match rx.recv() {
-            Ok(_) => {
-                println!("Changes detected, rebuilding site...");
+        let changed_path = match rx.recv() {
+            Ok(ChangeKind::Content(path)) => {
+                println!("Content changed ({:?}), rebuilding...", path);
+                if let Some(collection) = content_collection(&path) {
+                    build_content_file(&collection, &path).unwrap();
+                } else {
+                    build_site().unwrap();
+                }
+                path.to_string_lossy().into_owned()
+            }
+            Ok(ChangeKind::Templates) => {
+                println!("Templates changed, rebuilding whole site...");
                 build_site().unwrap();
+                "templates".to_string()
+            }
+            Ok(ChangeKind::StaticFiles(path)) => {
+                println!("Static file changed ({:?}), copying...", path);
+                copy_static_file(&path).unwrap();
+                path.to_string_lossy().into_owned()
+            }
+            Err(e) => {
+                println!("watch error: {:?}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = broadcaster.send(livereload_message(&changed_path)) {
+            println!("livereload broadcast error: {:?}", e);
+        }
+    }
+}
+
+fn start_server(config: Config) {
+    let listener = TcpListener::bind("127.0.0.1:7878").unwrap();
+    let tls_config = if config.tls { Some(tls::server_config().unwrap()) } else { None };
+    println!("Server listening on port 7878 ({})", if config.tls { "https" } else { "http" });
 
-                // Restart the server
-                let mut control = server_control.lock().unwrap();
-                *control = false;
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+        let tls_config = tls_config.clone();
+
+        thread::spawn(move || match tls_config {
+            Some(tls_config) => match tls::accept(tls_config, stream) {
+                Ok(mut tls_stream) => handle_connection(&mut tls_stream, config),
+                Err(e) => println!("tls handshake error: {:?}", e),
+            },
+            None => {
+                let mut stream = stream;
+                handle_connection(&mut stream, config);
+            }
+        });
+    }
+}
 
-                // Wait for the server to stop
-                thread::sleep(Duration::from_secs(1));
+/// A no-op LiveReload connection handler; the server only ever pushes
+/// `reload` commands out through the broadcaster; the only incoming message
+/// it ever expects is the client's own `hello`, which it has no need to
+/// inspect.
+struct ReloadHandler {
+    out: Sender,
+    ssl: Option<Arc<SslAcceptor>>,
+}
 
-                // Start a new server
-                let server_control_clone = Arc::clone(&server_control);
-                thread::spawn(move || {
-                    start_server(server_control_clone);
-                });
+impl ws::Handler for ReloadHandler {
+    fn on_open(&mut self, _handshake: ws::Handshake) -> ws::Result<()> {
+        self.out.send(hello_message())
+    }
 
-                *control = true;
+    fn on_message(&mut self, _msg: ws::Message) -> ws::Result<()> {
+        Ok(())
+    }
 
-                // Notify WebSocket clients to reload
-                NOTIFY_RELOAD.store(true, Ordering::Relaxed);
-            }
-            Err(e) => println!("watch error: {:?}", e),
-        }
+    fn upgrade_ssl_server(&mut self, sock: WsTcpStream) -> ws::Result<SslStream<WsTcpStream>> {
+        self.ssl
+            .as_ref()
+            .expect("upgrade_ssl_server called without a configured SslAcceptor")
+            .accept(sock)
+            .map_err(From::from)
     }
+}
 
-    // Join the server threads to keep the program running
-    server_thread.join().unwrap();
-    ws_thread.join().unwrap();
+/// Starts the LiveReload WebSocket server on its own thread and returns a
+/// broadcaster that reaches every connected client, captured once at
+/// startup instead of tracked in a shared client list. When `tls` is set,
+/// the socket terminates TLS itself (via `ws`'s OpenSSL support) so it
+/// matches the `wss://` URL `livereload_script` hands to the browser.
+#[allow(clippy::result_large_err)]
+fn start_ws_server(tls: bool) -> Sender {
+    let ssl = if tls { Some(tls::ws_acceptor().unwrap()) } else { None };
+
+    let settings = ws::Settings { encrypt_server: tls, ..ws::Settings::default() };
+    let ws_server = ws::Builder::new()
+        .with_settings(settings)
+        .build(move |out| ReloadHandler { out, ssl: ssl.clone() })
+        .unwrap();
+    let broadcaster = ws_server.broadcaster();
+
+    thread::spawn(move || {
+        ws_server.listen("127.0.0.1:7879").unwrap();
+    });
+
+    broadcaster
 }
 
-fn start_server(control: Arc<Mutex<bool>>) {
-    let listener = TcpListener::bind("127.0.0.1:7878").unwrap();
-    println!("Server listening on port 7878");
+/// Builds the server's LiveReload protocol `hello` handshake message, sent
+/// as soon as a client connects, per the official-7 protocol
+/// (http://livereload.com/protocols/official-7).
+fn hello_message() -> String {
+    r#"{"command":"hello","protocols":["http://livereload.com/protocols/official-7"],"serverName":"rust-static"}"#
+        .to_string()
+}
 
-    for stream in listener.incoming() {
-        let stream = stream.unwrap();
-        handle_connection(stream);
+/// Builds a LiveReload protocol `reload` command for `path`, per the
+/// official-7 protocol (http://livereload.com/protocols/official-7).
+fn livereload_message(path: &str) -> String {
+    format!(
+        r#"{{"command":"reload","path":"{}","liveCSS":true,"protocols":["http://livereload.com/protocols/official-7"]}}"#,
+        json_escape(path)
+    )
+}
 
-        let control = control.lock().unwrap();
-        if !*control {
-            break;
+/// Escapes `text` for embedding in a JSON string literal: backslashes,
+/// double quotes, and control characters.
+fn json_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
         }
     }
+    escaped
+}
 
-    println!("Server stopped.");
+/// The injected LiveReload client script. `tls` picks `wss://` over `ws://`
+/// so the WebSocket connection matches the page's own security context.
+fn livereload_script(tls: bool) -> String {
+    let scheme = if tls { "wss" } else { "ws" };
+    format!(
+        "<script>
+    const ws = new WebSocket('{scheme}://127.0.0.1:7879');
+    ws.onopen = () => {{
+        ws.send(JSON.stringify({{
+            command: 'hello',
+            protocols: ['http://livereload.com/protocols/official-7']
+        }}));
+    }};
+    ws.onmessage = (event) => {{
+        const message = JSON.parse(event.data);
+        if (message.command !== 'reload') {{
+            return;
+        }}
+
+        if (message.liveCSS && message.path.endsWith('.css')) {{
+            const link = document.querySelector(
+                `link[rel=\"stylesheet\"][href*=\"${{message.path.split('/').pop()}}\"]`
+            );
+            if (link) {{
+                const url = new URL(link.href, window.location.href);
+                url.searchParams.set('_livereload', Date.now());
+                link.href = url.toString();
+                return;
+            }}
+        }}
+
+        location.reload();
+    }};
+</script>",
+        scheme = scheme
+    )
 }
 
-fn start_ws_server() {
-    listen("127.0.0.1:7879", |out| {
-        WS_CLIENTS.lock().unwrap().push(out.clone());
-        move |msg| {
-            if NOTIFY_RELOAD.load(Ordering::Relaxed) {
-                out.send("reload").unwrap();
-                NOTIFY_RELOAD.store(false, Ordering::Relaxed);
-            }
-            Ok(())
+/// What a request path resolved to, once directories have been taken into
+/// account.
+enum Resolved {
+    File(PathBuf),
+    Listing(PathBuf),
+}
+
+/// Resolves a request path on disk: a concrete file serves as-is, a
+/// directory serves its `index.html` if present, and otherwise serves an
+/// auto-generated listing when `auto_index` is enabled (404s otherwise).
+fn resolve(path: &Path, auto_index: bool) -> Option<Resolved> {
+    if path.is_dir() {
+        let index = path.join("index.html");
+        if index.exists() {
+            Some(Resolved::File(index))
+        } else if auto_index {
+            Some(Resolved::Listing(path.to_path_buf()))
+        } else {
+            None
         }
-    }).unwrap();
+    } else if path.exists() {
+        Some(Resolved::File(path.to_path_buf()))
+    } else {
+        None
+    }
 }
 
-fn handle_connection(mut stream: TcpStream) {
-    let mut buffer = [0; 1024];
-    stream.read(&mut buffer).unwrap();
+fn handle_connection<S: Read + Write>(stream: &mut S, config: Config) {
+    let request = match read_request(stream) {
+        Ok(request) => request,
+        Err(_) => return,
+    };
+    if request.is_empty() {
+        // A connection that closed without sending anything (port scanners,
+        // LB health checks, a browser's speculative TCP connect) has no
+        // request line to read.
+        return;
+    }
 
-    let get = b"GET / HTTP/1.1\r\n";
-    let (status_line, filename) = if buffer.starts_with(get) {
-        ("HTTP/1.1 200 OK\r\n\r\n", "output/pages/index.html".to_string())
+    let path = request.lines().next().unwrap_or("").split_whitespace().nth(1).unwrap_or("/");
+    let filepath = if path == "/" {
+        "output/pages/index.html".to_string()
     } else {
-        // Extract the requested path from the request buffer
-        let request = String::from_utf8_lossy(&buffer[..]);
-        let path = request.lines().next().unwrap().split_whitespace().nth(1).unwrap();
-        let filepath = format!("output{}", path);
-        let file_path = Path::new(&filepath);
-
-        if file_path.exists() {
-            ("HTTP/1.1 200 OK\r\n\r\n", filepath)
-        } else {
-            ("HTTP/1.1 404 NOT FOUND\r\n\r\n", "output/pages/404.html".to_string())
+        format!("output{}", path)
+    };
+    let file_path = Path::new(&filepath);
+
+    let (status_line, content_type, mut body) = match resolve(file_path, config.auto_index) {
+        Some(Resolved::File(served_path)) => {
+            ("HTTP/1.1 200 OK", content_type_for(&served_path), fs::read(&served_path).unwrap())
+        }
+        Some(Resolved::Listing(dir)) => {
+            let listing_html = listing::render_directory_listing(&dir).unwrap();
+            let display_path = dir.strip_prefix("output").unwrap_or(&dir).to_string_lossy().into_owned();
+            let mut data = HashMap::new();
+            data.insert("title".to_string(), format!("Index of /{}", display_path.trim_start_matches('/')));
+            let html = apply_template("listing.html", &listing_html, &data);
+            ("HTTP/1.1 200 OK", "text/html", html.into_bytes())
+        }
+        None => {
+            let not_found = Path::new("output/pages/404.html");
+            ("HTTP/1.1 404 NOT FOUND", content_type_for(not_found), fs::read(not_found).unwrap())
         }
     };
 
-    let mut contents = fs::read_to_string(filename).unwrap();
+    if content_type == "text/html" {
+        body.extend_from_slice(livereload_script(config.tls).as_bytes());
+    }
 
-    // Inject JavaScript for auto reload
-    contents.push_str(
-        "<script>
-            const ws = new WebSocket('ws://127.0.0.1:7879');
-            ws.onmessage = (event) => {
-                if (event.data === 'reload') {
-                    location.reload();
-                }
-            };
-        </script>"
+    let headers = format!(
+        "{}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n",
+        status_line,
+        content_type,
+        body.len()
     );
 
-    let response = format!("{}{}", status_line, contents);
-
-    stream.write(response.as_bytes()).unwrap();
+    stream.write_all(headers.as_bytes()).unwrap();
+    stream.write_all(&body).unwrap();
     stream.flush().unwrap();
 }
 
+/// Reads a full HTTP request from `stream`, growing the buffer until the
+/// header terminator (`\r\n\r\n`) is seen so long request lines aren't
+/// truncated like a fixed-size read would.
+fn read_request<S: Read>(stream: &mut S) -> std::io::Result<String> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 1024];
+
+    loop {
+        let n = stream.read(&mut chunk)?;
+        if n == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+
+        if buffer.windows(4).any(|w| w == b"\r\n\r\n") {
+            break;
+        }
+    }
+
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
+
 fn build_site() -> std::io::Result<()> {
     let collections = vec!["pages", "projects"];
 
@@ -149,12 +372,7 @@ fn build_site() -> std::io::Result<()> {
             let path = entry.path();
 
             if path.is_file() {
-                let filename = path.file_stem().unwrap().to_str().unwrap();
-                let markdown_content = fs::read_to_string(&path)?;
-                let html_content = markdown_to_html(&markdown_content);
-                let output_file = format!("{}/{}.html", output_path, filename);
-                let html = apply_template(&format!("{}.html", collection), &html_content);
-                fs::write(output_file, html)?;
+                build_content_file(collection, &path)?;
             }
         }
     }
@@ -162,75 +380,89 @@ fn build_site() -> std::io::Result<()> {
     Ok(())
 }
 
-fn markdown_to_html(markdown: &str) -> String {
-    let mut html = String::new();
-    for line in markdown.lines() {
-        if line.starts_with("# ") {
-            html.push_str(&format!("<h1>{}</h1>\n", &line[2..]));
-        } else if line.starts_with("## ") {
-            html.push_str(&format!("<h2>{}</h2>\n", &line[3..]));
-        } else if line.starts_with("### ") {
-            html.push_str(&format!("<h3>{}</h3>\n", &line[4..]));
-        } else if line.starts_with("#### ") {
-            html.push_str(&format!("<h4>{}</h4>\n", &line[5..]));
-        } else if line.starts_with("##### ") {
-            html.push_str(&format!("<h5>{}</h5>\n", &line[6..]));
-        } else if line.starts_with("###### ") {
-            html.push_str(&format!("<h6>{}</h6>\n", &line[7..]));
-        } else if line.starts_with("[") && line.contains("](") {
-            let end_bracket = line.find(']').unwrap();
-            let start_paren = line.find('(').unwrap();
-            let end_paren = line.find(')').unwrap();
-            let text = &line[1..end_bracket];
-            let url = &line[start_paren + 1..end_paren];
-            html.push_str(&format!("<a href=\"{}\">{}</a>\n", url, text));
-        } else {
-            html.push_str(&format!("<p>{}</p>\n", line));
-        }
+/// Rebuilds a single content file into its corresponding output page.
+/// Used for incremental rebuilds when only one file under `content/` changes.
+fn build_content_file(collection: &str, path: &Path) -> std::io::Result<()> {
+    let raw = fs::read_to_string(path)?;
+    let page = frontmatter::parse(&raw);
+
+    if is_draft(&page.data) {
+        return Ok(());
     }
-    html
+
+    let output_path = format!("output/{}", collection);
+    fs::create_dir_all(&output_path)?;
+
+    let template_name = page
+        .data
+        .get("template")
+        .map(|name| if name.ends_with(".html") { name.clone() } else { format!("{}.html", name) })
+        .unwrap_or_else(|| format!("{}.html", collection));
+
+    let filename = path.file_stem().unwrap().to_str().unwrap();
+    let html_content = markdown_to_html(&page.body);
+    let output_file = format!("{}/{}.html", output_path, filename);
+    let html = apply_template(&template_name, &html_content, &page.data);
+    write_atomic(Path::new(&output_file), html.as_bytes())
 }
 
-fn apply_template(template_name: &str, content: &str) -> String {
+/// Writes `contents` to a sibling temp file and renames it into place, so a
+/// request handled concurrently with a rebuild never reads a half-written
+/// file (rebuilds and request handling now run on independent threads).
+fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, contents)?;
+    fs::rename(&tmp_path, path)
+}
+
+/// Copies a single file under `static/` through to the matching path under
+/// `output/static/`, mirroring the directory structure.
+fn copy_static_file(path: &Path) -> std::io::Result<()> {
+    let relative = path.strip_prefix("static").unwrap_or(path);
+    let dest = Path::new("output/static").join(relative);
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let contents = fs::read(path)?;
+    write_atomic(&dest, &contents)?;
+    fs::set_permissions(&dest, fs::metadata(path)?.permissions())
+}
+
+fn apply_template(template_name: &str, content: &str, data: &HashMap<String, String>) -> String {
     let template_path = format!("templates/{}", template_name);
     let template = fs::read_to_string(template_path).unwrap();
-    
+
     // Apply the collection template
     let collection_content = template.replace("{{ content }}", content);
-    
+
     // Apply the base template
     let base_template = fs::read_to_string("templates/base.html").unwrap();
-    base_template.replace("{{ content }}", &collection_content)
-                 .replace("{{ title }}", "My Site")
-}
-
-fn watch_content_directory(tx: mpsc::Sender<()>) {
-    let mut last_modified = SystemTime::now();
-
-    loop {
-        thread::sleep(Duration::from_secs(2));
-
-        let mut changed = false;
-        for entry in fs::read_dir("content").unwrap() {
-            let entry = entry.unwrap();
-            let metadata = fs::metadata(entry.path()).unwrap();
-            let modified = metadata.modified().unwrap();
+    let html = base_template.replace("{{ content }}", &collection_content);
 
-            if modified > last_modified {
-                changed = true;
-                last_modified = modified;
-            }
-        }
+    substitute_placeholders(&html, data)
+}
 
-        if changed {
-            tx.send(()).unwrap();
-        }
+/// Substitutes every `{{ key }}` found in `template` with the matching
+/// front-matter value, falling back to a default `title` when the page
+/// doesn't declare one.
+fn substitute_placeholders(template: &str, data: &HashMap<String, String>) -> String {
+    let mut html = template.to_string();
+    for (key, value) in data {
+        html = html.replace(&format!("{{{{ {} }}}}", key), value);
+    }
+    if !data.contains_key("title") {
+        html = html.replace("{{ title }}", "My Site");
     }
+    html
 }
 
-// Globals for WebSocket reload notification
-lazy_static! {
-    static ref WS_CLIENTS: Mutex<Vec<Sender>> = Mutex::new(Vec::new());
-    static ref NOTIFY_RELOAD: AtomicBool = AtomicBool::new(false);
+/// Returns the collection name (e.g. `"pages"`) a changed content path
+/// belongs to, i.e. the first path component under `content/`.
+fn content_collection(path: &Path) -> Option<String> {
+    let relative = path.strip_prefix("content").ok()?;
+    relative
+        .components()
+        .next()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
 }
 
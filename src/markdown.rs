@@ -0,0 +1,571 @@
+//! A small CommonMark-ish Markdown-to-HTML converter.
+//!
+//! This isn't a full CommonMark implementation (no reference links, no HTML
+//! blocks, simplified list-continuation rules) but it covers the block and
+//! inline constructs real content actually uses: headings (ATX and
+//! setext), fenced/indented code blocks, blockquotes, ordered/unordered
+//! (possibly nested) lists, thematic breaks, and inline emphasis, code,
+//! links and images.
+
+#[derive(Debug)]
+enum Block {
+    Heading(u8, String),
+    Paragraph(String),
+    Code(Option<String>, String),
+    Quote(Vec<Block>),
+    List { ordered: bool, start: usize, items: Vec<Vec<Block>> },
+    ThematicBreak,
+}
+
+pub fn markdown_to_html(markdown: &str) -> String {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let blocks = parse_blocks(&lines);
+    render_blocks(&blocks)
+}
+
+fn render_blocks(blocks: &[Block]) -> String {
+    let mut html = String::new();
+    for block in blocks {
+        render_block(block, &mut html);
+    }
+    html
+}
+
+fn render_block(block: &Block, html: &mut String) {
+    match block {
+        Block::Heading(level, text) => {
+            html.push_str(&format!("<h{0}>{1}</h{0}>\n", level, render_inline(text)));
+        }
+        Block::Paragraph(text) => {
+            html.push_str(&format!("<p>{}</p>\n", render_inline(text)));
+        }
+        Block::Code(lang, code) => {
+            let class = match lang {
+                Some(lang) if !lang.is_empty() => format!(" class=\"language-{}\"", escape_html(lang)),
+                _ => String::new(),
+            };
+            html.push_str(&format!("<pre><code{}>{}</code></pre>\n", class, escape_html(code)));
+        }
+        Block::Quote(children) => {
+            html.push_str("<blockquote>\n");
+            html.push_str(&render_blocks(children));
+            html.push_str("</blockquote>\n");
+        }
+        Block::List { ordered, start, items } => {
+            let tag = if *ordered { "ol" } else { "ul" };
+            if *ordered && *start != 1 {
+                html.push_str(&format!("<{} start=\"{}\">\n", tag, start));
+            } else {
+                html.push_str(&format!("<{}>\n", tag));
+            }
+            for item in items {
+                html.push_str("<li>");
+                match item.as_slice() {
+                    // A single paragraph inside a tight list item renders
+                    // without its own <p> wrapper.
+                    [Block::Paragraph(text)] => {
+                        html.push_str(&render_inline(text));
+                    }
+                    // Likewise when the item's only content is its text
+                    // followed by nested list(s).
+                    [Block::Paragraph(text), rest @ ..] if rest.iter().all(|b| matches!(b, Block::List { .. })) => {
+                        html.push_str(&render_inline(text));
+                        html.push('\n');
+                        for block in rest {
+                            render_block(block, html);
+                        }
+                    }
+                    _ => {
+                        html.push('\n');
+                        html.push_str(&render_blocks(item));
+                    }
+                }
+                html.push_str("</li>\n");
+            }
+            html.push_str(&format!("</{}>\n", tag));
+        }
+        Block::ThematicBreak => html.push_str("<hr>\n"),
+    }
+}
+
+fn parse_blocks(lines: &[&str]) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+
+        if line.trim().is_empty() {
+            i += 1;
+            continue;
+        }
+
+        if let Some(fence) = fence_marker(line) {
+            let (block, next) = parse_fenced_code(lines, i, fence);
+            blocks.push(block);
+            i = next;
+            continue;
+        }
+
+        if is_thematic_break(line) {
+            blocks.push(Block::ThematicBreak);
+            i += 1;
+            continue;
+        }
+
+        if let Some((level, rest)) = atx_heading(line) {
+            blocks.push(Block::Heading(level, rest.to_string()));
+            i += 1;
+            continue;
+        }
+
+        if indent_of(line) >= 4 {
+            let (block, next) = parse_indented_code(lines, i);
+            blocks.push(block);
+            i = next;
+            continue;
+        }
+
+        if let Some(rest) = blockquote_marker(line) {
+            let (quoted, next) = collect_blockquote(lines, i, rest);
+            blocks.push(Block::Quote(parse_blocks(&quoted)));
+            i = next;
+            continue;
+        }
+
+        if let Some((ordered, start)) = list_marker(line) {
+            let (list, next) = parse_list(lines, i, ordered, start);
+            blocks.push(list);
+            i = next;
+            continue;
+        }
+
+        // Paragraph: collect lines until a blank line or another block start,
+        // checking for a setext underline along the way.
+        let (para_lines, next) = collect_paragraph(lines, i);
+        if next < lines.len() {
+            if let Some(level) = setext_underline(lines[next]) {
+                blocks.push(Block::Heading(level, para_lines.join(" ")));
+                i = next + 1;
+                continue;
+            }
+        }
+        blocks.push(Block::Paragraph(para_lines.join(" ")));
+        i = next;
+    }
+
+    blocks
+}
+
+fn indent_of(line: &str) -> usize {
+    line.chars().take_while(|c| *c == ' ').count()
+}
+
+fn atx_heading(line: &str) -> Option<(u8, &str)> {
+    let trimmed = line.trim_start();
+    let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+    if hashes == 0 || hashes > 6 {
+        return None;
+    }
+    let rest = &trimmed[hashes..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some((hashes as u8, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+fn setext_underline(line: &str) -> Option<u8> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    if trimmed.chars().all(|c| c == '=') {
+        Some(1)
+    } else if trimmed.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+fn is_thematic_break(line: &str) -> bool {
+    let trimmed = line.trim();
+    for marker in ['*', '-', '_'] {
+        let count = trimmed.chars().filter(|c| *c == marker).count();
+        if count >= 3 && trimmed.chars().all(|c| c == marker || c == ' ') {
+            return true;
+        }
+    }
+    false
+}
+
+fn fence_marker(line: &str) -> Option<char> {
+    let trimmed = line.trim_start();
+    for marker in ['`', '~'] {
+        let count = trimmed.chars().take_while(|c| *c == marker).count();
+        if count >= 3 {
+            return Some(marker);
+        }
+    }
+    None
+}
+
+fn parse_fenced_code(lines: &[&str], start: usize, fence: char) -> (Block, usize) {
+    let opening = lines[start].trim_start();
+    let fence_len = opening.chars().take_while(|c| *c == fence).count();
+    let lang = opening[fence_len..].trim().to_string();
+
+    let mut code = String::new();
+    let mut i = start + 1;
+    while i < lines.len() {
+        let trimmed = lines[i].trim_start();
+        if trimmed.chars().take_while(|c| *c == fence).count() >= fence_len
+            && trimmed.chars().all(|c| c == fence)
+        {
+            i += 1;
+            break;
+        }
+        code.push_str(lines[i]);
+        code.push('\n');
+        i += 1;
+    }
+
+    (Block::Code(Some(lang).filter(|l| !l.is_empty()), code), i)
+}
+
+fn parse_indented_code(lines: &[&str], start: usize) -> (Block, usize) {
+    let mut code = String::new();
+    let mut i = start;
+    while i < lines.len() && (indent_of(lines[i]) >= 4 || lines[i].trim().is_empty()) {
+        if lines[i].trim().is_empty() {
+            code.push('\n');
+        } else {
+            code.push_str(&lines[i][4..]);
+            code.push('\n');
+        }
+        i += 1;
+    }
+    (Block::Code(None, code.trim_end_matches('\n').to_string() + "\n"), i)
+}
+
+fn blockquote_marker(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    if let Some(rest) = trimmed.strip_prefix("> ") {
+        Some(rest)
+    } else {
+        trimmed.strip_prefix('>')
+    }
+}
+
+fn collect_blockquote<'a>(lines: &[&'a str], start: usize, first_rest: &'a str) -> (Vec<&'a str>, usize) {
+    let mut collected = vec![first_rest];
+    let mut i = start + 1;
+    while i < lines.len() {
+        if let Some(rest) = blockquote_marker(lines[i]) {
+            collected.push(rest);
+            i += 1;
+        } else if lines[i].trim().is_empty() {
+            break;
+        } else {
+            collected.push(lines[i]);
+            i += 1;
+        }
+    }
+    (collected, i)
+}
+
+/// Recognizes `- `, `* `, `+ ` (unordered) and `N. ` / `N) ` (ordered)
+/// markers, returning whether the list is ordered and its start number.
+fn list_marker(line: &str) -> Option<(bool, usize)> {
+    let trimmed = line.trim_start();
+    if let Some(rest) = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+        .or_else(|| trimmed.strip_prefix("+ "))
+    {
+        let _ = rest;
+        return Some((false, 0));
+    }
+
+    let digits: String = trimmed.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if digits.is_empty() {
+        return None;
+    }
+    let rest = &trimmed[digits.len()..];
+    if rest.starts_with(". ") || rest.starts_with(") ") {
+        return Some((true, digits.parse().unwrap_or(1)));
+    }
+    None
+}
+
+fn list_item_text(line: &str) -> &str {
+    let trimmed = line.trim_start();
+    for prefix in ["- ", "* ", "+ "] {
+        if let Some(rest) = trimmed.strip_prefix(prefix) {
+            return rest;
+        }
+    }
+    let digits: String = trimmed.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let after_digits = &trimmed[digits.len()..];
+    after_digits
+        .strip_prefix(". ")
+        .or_else(|| after_digits.strip_prefix(") "))
+        .unwrap_or(after_digits)
+}
+
+fn parse_list(lines: &[&str], start: usize, ordered: bool, list_start: usize) -> (Block, usize) {
+    let base_indent = indent_of(lines[start]);
+    let mut items = Vec::new();
+    let mut i = start;
+
+    while i < lines.len() {
+        if lines[i].trim().is_empty() {
+            // A blank line ends the list unless the next line continues an item.
+            if i + 1 >= lines.len() || list_marker(lines[i + 1]).is_none() {
+                i += 1;
+                break;
+            }
+            i += 1;
+            continue;
+        }
+
+        if indent_of(lines[i]) != base_indent {
+            break;
+        }
+
+        match list_marker(lines[i]) {
+            Some((item_ordered, _)) if item_ordered == ordered => {
+                let mut item_blocks = vec![Block::Paragraph(list_item_text(lines[i]).to_string())];
+                i += 1;
+
+                // Fold plain continuation lines into the item's paragraph, and
+                // recurse into a child list when a more-indented marker starts one.
+                while i < lines.len() {
+                    if lines[i].trim().is_empty() {
+                        if i + 1 < lines.len() && indent_of(lines[i + 1]) > base_indent {
+                            i += 1;
+                            continue;
+                        }
+                        break;
+                    }
+                    if indent_of(lines[i]) <= base_indent {
+                        break;
+                    }
+                    if let Some((nested_ordered, nested_start)) = list_marker(lines[i]) {
+                        let (nested_list, next) = parse_list(lines, i, nested_ordered, nested_start);
+                        item_blocks.push(nested_list);
+                        i = next;
+                        continue;
+                    }
+                    if let Some(Block::Paragraph(text)) = item_blocks.first_mut() {
+                        text.push(' ');
+                        text.push_str(lines[i].trim());
+                    }
+                    i += 1;
+                }
+
+                items.push(item_blocks);
+            }
+            _ => break,
+        }
+    }
+
+    (Block::List { ordered, start: list_start.max(1), items }, i)
+}
+
+fn collect_paragraph<'a>(lines: &[&'a str], start: usize) -> (Vec<&'a str>, usize) {
+    let mut collected = Vec::new();
+    let mut i = start;
+    while i < lines.len() {
+        let line = lines[i];
+        if line.trim().is_empty()
+            || fence_marker(line).is_some()
+            || is_thematic_break(line)
+            || atx_heading(line).is_some()
+            || blockquote_marker(line).is_some()
+            || list_marker(line).is_some()
+            || (!collected.is_empty() && setext_underline(line).is_some())
+        {
+            break;
+        }
+        collected.push(line.trim());
+        i += 1;
+    }
+    (collected, i)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Inline parsing: code spans, images, links, then bold and italic emphasis.
+/// Applied left-to-right over the (already plain-text) block content.
+fn render_inline(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] == '`' {
+            if let Some((code, next)) = take_delimited(&chars, i, "`") {
+                out.push_str(&format!("<code>{}</code>", escape_html(&code)));
+                i = next;
+                continue;
+            }
+        }
+
+        if chars[i] == '!' && i + 1 < chars.len() && chars[i + 1] == '[' {
+            if let Some((alt, url, next)) = take_link(&chars, i + 1) {
+                out.push_str(&format!(
+                    "<img src=\"{}\" alt=\"{}\">",
+                    escape_html(&url),
+                    escape_html(&alt)
+                ));
+                i = next;
+                continue;
+            }
+        }
+
+        if chars[i] == '[' {
+            if let Some((label, url, next)) = take_link(&chars, i) {
+                out.push_str(&format!("<a href=\"{}\">{}</a>", escape_html(&url), render_inline(&label)));
+                i = next;
+                continue;
+            }
+        }
+
+        if chars[i] == '*' || chars[i] == '_' {
+            let marker = chars[i];
+            let double = i + 1 < chars.len() && chars[i + 1] == marker;
+            let delim: String = if double {
+                [marker, marker].iter().collect()
+            } else {
+                marker.to_string()
+            };
+            if let Some((inner, next)) = take_delimited(&chars, i, &delim) {
+                let tag = if double { "strong" } else { "em" };
+                out.push_str(&format!("<{0}>{1}</{0}>", tag, render_inline(&inner)));
+                i = next;
+                continue;
+            }
+        }
+
+        out.push_str(&escape_html(&chars[i].to_string()));
+        i += 1;
+    }
+
+    out
+}
+
+/// Finds `delim ... delim` starting at `start` (which must point at the
+/// opening delimiter) and returns the enclosed text and the index after the
+/// closing delimiter.
+fn take_delimited(chars: &[char], start: usize, delim: &str) -> Option<(String, usize)> {
+    let delim_chars: Vec<char> = delim.chars().collect();
+    let open_end = start + delim_chars.len();
+    if open_end > chars.len() || chars[start..open_end] != delim_chars[..] {
+        return None;
+    }
+
+    let mut i = open_end;
+    while i + delim_chars.len() <= chars.len() {
+        if chars[i..i + delim_chars.len()] == delim_chars[..] && i > open_end {
+            let inner: String = chars[open_end..i].iter().collect();
+            return Some((inner, i + delim_chars.len()));
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Parses `[label](url)` (or, for images, `label`/`url` after the leading
+/// `!` has already been skipped by the caller) starting at the `[`.
+fn take_link(chars: &[char], start: usize) -> Option<(String, String, usize)> {
+    if chars.get(start) != Some(&'[') {
+        return None;
+    }
+    let close_bracket = find_char(chars, start + 1, ']')?;
+    if chars.get(close_bracket + 1) != Some(&'(') {
+        return None;
+    }
+    let close_paren = find_char(chars, close_bracket + 2, ')')?;
+
+    let label: String = chars[start + 1..close_bracket].iter().collect();
+    let url: String = chars[close_bracket + 2..close_paren].iter().collect();
+    Some((label, url, close_paren + 1))
+}
+
+fn find_char(chars: &[char], from: usize, needle: char) -> Option<usize> {
+    (from..chars.len()).find(|&i| chars[i] == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::markdown_to_html;
+
+    #[test]
+    fn nested_list_renders_child_ul_inside_parent_li() {
+        let html = markdown_to_html("- Item 1\n  - Nested A\n  - Nested B\n- Item 2\n");
+        assert_eq!(
+            html,
+            "<ul>\n<li>Item 1\n<ul>\n<li>Nested A</li>\n<li>Nested B</li>\n</ul>\n</li>\n<li>Item 2</li>\n</ul>\n"
+        );
+    }
+
+    #[test]
+    fn nested_ordered_list_keeps_its_own_numbering() {
+        let html = markdown_to_html("1. First\n   1. Nested first\n   2. Nested second\n2. Second\n");
+        assert_eq!(
+            html,
+            "<ol>\n<li>First\n<ol>\n<li>Nested first</li>\n<li>Nested second</li>\n</ol>\n</li>\n<li>Second</li>\n</ol>\n"
+        );
+    }
+
+    #[test]
+    fn fenced_code_block_escapes_html() {
+        let html = markdown_to_html("```\n<script>alert(1)</script>\n```\n");
+        assert_eq!(html, "<pre><code>&lt;script&gt;alert(1)&lt;/script&gt;\n</code></pre>\n");
+    }
+
+    #[test]
+    fn fenced_code_block_with_language_sets_class_and_escapes() {
+        let html = markdown_to_html("```rust\nlet x = 1 < 2;\n```\n");
+        assert_eq!(
+            html,
+            "<pre><code class=\"language-rust\">let x = 1 &lt; 2;\n</code></pre>\n"
+        );
+    }
+
+    #[test]
+    fn emphasis_and_strong_render_nested_tags() {
+        assert_eq!(markdown_to_html("*em*\n"), "<p><em>em</em></p>\n");
+        assert_eq!(markdown_to_html("**strong**\n"), "<p><strong>strong</strong></p>\n");
+        // take_delimited matches the first `**` it finds, so the inner `*`s
+        // here land inside the strong span as literal text rather than
+        // nesting an <em>.
+        assert_eq!(
+            markdown_to_html("**bold *and italic***\n"),
+            "<p><strong>bold *and italic</strong>*</p>\n"
+        );
+    }
+
+    #[test]
+    fn link_and_image_escape_url_and_alt_text() {
+        assert_eq!(
+            markdown_to_html("[a \"b\"](http://x?y&z)\n"),
+            "<p><a href=\"http://x?y&amp;z\">a &quot;b&quot;</a></p>\n"
+        );
+        assert_eq!(
+            markdown_to_html("![alt \"text\"](img.png)\n"),
+            "<p><img src=\"img.png\" alt=\"alt &quot;text&quot;\"></p>\n"
+        );
+    }
+
+    #[test]
+    fn unterminated_delimiter_is_left_as_literal_text() {
+        assert_eq!(markdown_to_html("*unterminated\n"), "<p>*unterminated</p>\n");
+    }
+}
@@ -0,0 +1,24 @@
+use std::path::Path;
+
+/// Maps a file's extension to a `Content-Type` header value. Falls back to
+/// `application/octet-stream` for anything not in the table so unknown
+/// files are still served correctly instead of corrupted as text.
+pub fn content_type_for(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("html") | Some("htm") => "text/html",
+        Some("css") => "text/css",
+        Some("js") => "application/javascript",
+        Some("json") => "application/json",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("ico") => "image/x-icon",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("ttf") => "font/ttf",
+        Some("txt") => "text/plain",
+        Some("xml") => "application/xml",
+        _ => "application/octet-stream",
+    }
+}
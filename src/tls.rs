@@ -0,0 +1,95 @@
+use std::fs;
+use std::io;
+use std::net::TcpStream;
+use std::path::Path;
+use std::sync::Arc;
+
+use openssl::pkey::PKey;
+use openssl::ssl::{SslAcceptor, SslMethod};
+use openssl::x509::X509;
+use rustls::{Certificate, PrivateKey, ServerConfig, ServerConnection, StreamOwned};
+
+/// An accepted TLS connection, read/write just like a plain `TcpStream`.
+pub type TlsStream = StreamOwned<ServerConnection, TcpStream>;
+
+const CERT_PATH: &str = "tls/cert.pem";
+const KEY_PATH: &str = "tls/key.pem";
+
+/// Builds the `rustls` server config for the dev server, generating a
+/// self-signed certificate under `tls/` on first run so `--tls` works with
+/// no manual setup.
+pub fn server_config() -> io::Result<Arc<ServerConfig>> {
+    ensure_cert()?;
+
+    let certs = load_certs(CERT_PATH)?;
+    let key = load_private_key(KEY_PATH)?;
+
+    let config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    Ok(Arc::new(config))
+}
+
+fn ensure_cert() -> io::Result<()> {
+    if !Path::new(CERT_PATH).exists() || !Path::new(KEY_PATH).exists() {
+        generate_self_signed_cert()?;
+    }
+    Ok(())
+}
+
+fn generate_self_signed_cert() -> io::Result<()> {
+    fs::create_dir_all("tls")?;
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()])
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    let cert_pem = cert
+        .serialize_pem()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    fs::write(CERT_PATH, cert_pem)?;
+    fs::write(KEY_PATH, cert.serialize_private_key_pem())?;
+    println!("Generated self-signed TLS certificate at {}", CERT_PATH);
+    Ok(())
+}
+
+fn load_certs(path: &str) -> io::Result<Vec<Certificate>> {
+    let pem = fs::read(path)?;
+    let certs = rustls_pemfile::certs(&mut pem.as_slice())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid certificate"))?;
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn load_private_key(path: &str) -> io::Result<PrivateKey> {
+    let pem = fs::read(path)?;
+    let mut keys = rustls_pemfile::pkcs8_private_keys(&mut pem.as_slice())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid private key"))?;
+    keys.pop()
+        .map(PrivateKey)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no private key found in tls/key.pem"))
+}
+
+/// Wraps an accepted `TcpStream` in a `rustls` server connection, performing
+/// the TLS handshake as the stream is used.
+pub fn accept(config: Arc<ServerConfig>, stream: TcpStream) -> io::Result<TlsStream> {
+    let conn = ServerConnection::new(config).map_err(|e| io::Error::other(e.to_string()))?;
+    Ok(StreamOwned::new(conn, stream))
+}
+
+/// Builds an OpenSSL acceptor for the LiveReload WebSocket server, reusing
+/// the same self-signed certificate as the HTTP listener. The `ws` crate's
+/// built-in SSL support is implemented on top of OpenSSL rather than
+/// `rustls`, so this is a second, smaller TLS path alongside `server_config`.
+pub fn ws_acceptor() -> io::Result<Arc<SslAcceptor>> {
+    ensure_cert()?;
+
+    let cert = X509::from_pem(&fs::read(CERT_PATH)?).map_err(|e| io::Error::other(e.to_string()))?;
+    let key = PKey::private_key_from_pem(&fs::read(KEY_PATH)?).map_err(|e| io::Error::other(e.to_string()))?;
+
+    let mut builder =
+        SslAcceptor::mozilla_intermediate(SslMethod::tls()).map_err(|e| io::Error::other(e.to_string()))?;
+    builder.set_private_key(&key).map_err(|e| io::Error::other(e.to_string()))?;
+    builder.set_certificate(&cert).map_err(|e| io::Error::other(e.to_string()))?;
+
+    Ok(Arc::new(builder.build()))
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Sender};
+use std::time::{Duration, Instant};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+/// What part of the site a detected filesystem change affects.
+///
+/// The rebuild loop in `main` reacts differently to each kind: a `Content`
+/// change only needs to rebuild the affected file, a `Templates` change
+/// forces a full rebuild (any page may use the changed template), and a
+/// `StaticFiles` change is just copied straight through.
+#[derive(Debug, Clone)]
+pub enum ChangeKind {
+    Content(PathBuf),
+    Templates,
+    StaticFiles(PathBuf),
+}
+
+/// Recursively watches `content/`, `templates/`, and `static/` and sends a
+/// classified `ChangeKind` over `tx` for each distinct change, debouncing
+/// bursts of events (e.g. editors that write a file in several steps) so a
+/// single save doesn't trigger multiple rebuilds.
+pub fn watch_directories(tx: Sender<ChangeKind>) -> notify::Result<()> {
+    let (raw_tx, raw_rx) = channel();
+    let mut watcher: RecommendedWatcher = Watcher::new(raw_tx, notify::Config::default())?;
+
+    for dir in ["content", "templates", "static"] {
+        let path = Path::new(dir);
+        if path.exists() {
+            watcher.watch(path, RecursiveMode::Recursive)?;
+        }
+    }
+
+    const DEBOUNCE: Duration = Duration::from_millis(200);
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+
+    loop {
+        // Block for the first event of a burst, then drain whatever else
+        // arrives within the debounce window before classifying and sending.
+        let first = match raw_rx.recv() {
+            Ok(event) => event,
+            Err(_) => return Ok(()),
+        };
+
+        pending.clear();
+        for path in paths_of(first) {
+            pending.insert(path, Instant::now());
+        }
+
+        while let Ok(event) = raw_rx.recv_timeout(DEBOUNCE) {
+            for path in paths_of(event) {
+                pending.insert(path, Instant::now());
+            }
+        }
+
+        for path in pending.keys() {
+            // A delete (including the remove-half of an editor's atomic save)
+            // leaves nothing on disk to rebuild from, so skip it rather than
+            // classifying it like a create/modify.
+            if !path.exists() {
+                continue;
+            }
+            if let Some(kind) = classify(path) {
+                let _ = tx.send(kind);
+            }
+        }
+    }
+}
+
+fn paths_of(event: notify::Result<notify::Event>) -> Vec<PathBuf> {
+    match event {
+        Ok(event) => event.paths,
+        Err(_) => Vec::new(),
+    }
+}
+
+fn classify(path: &Path) -> Option<ChangeKind> {
+    if path.starts_with("templates") {
+        Some(ChangeKind::Templates)
+    } else if path.starts_with("content") {
+        Some(ChangeKind::Content(path.to_path_buf()))
+    } else if path.starts_with("static") {
+        Some(ChangeKind::StaticFiles(path.to_path_buf()))
+    } else {
+        None
+    }
+}